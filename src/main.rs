@@ -5,10 +5,19 @@
 //!
 //! Controls:
 //! - *Edit Mode:* Left-click to create a tile. Right-click to remove a tile.
-//! `x` to clear all tiles. `SPACE` to enter *Automata Mode*.
-//! - *Automata Mode:* `SPACE` to re-enter *Edit Mode*. The program should also
-//! automatically return to *Edit Mode* when there are either no living elements
-//! or no moving elements on screen.
+//! `x` to clear all tiles. `t` to toggle toroidal wrapping at the grid edges.
+//! `r` (Conway rule only) to fill the grid with a random soup at the current density, `+`/`-` to
+//! adjust that density. `m` to cycle between the Conway rule, the
+//! predator-prey *Ecosystem* rule, and the recursive *Fractal* rule; in
+//! Ecosystem mode, `c` cycles which species left-click paints. `SPACE` to
+//! enter *Automata Mode*.
+//! - *Automata Mode:* `SPACE` to re-enter *Edit Mode*. `p` to pause or resume
+//! the simulation. `.` advances exactly one generation while paused. `[`/`]`
+//! halve/double the tick interval. The program should also automatically
+//! return to *Edit Mode* when there are either no living elements or no
+//! moving elements anywhere in the simulation grid.
+//! - *Both Modes:* The simulation grid is much larger than the screen.
+//! Arrow keys/WASD pan the camera and the scroll wheel zooms, in both modes.
 //!
 //! **Author:** Jude Muriithi (GitHub:
 //! [jkmuriithi](https://github.com/jkmuriithi))
@@ -22,142 +31,492 @@
 
 use std::time::Instant;
 
+use rand::Rng;
 use raylib::consts::MouseButton::*;
 use raylib::prelude::*;
 
 // Game configuration constants
 const TICKS_PER_SECOND: u128 = 10;
-const GRID_SCALE: i32 = 20;
-const GRID_WIDTH: i32 = 40;
-const GRID_HEIGHT: i32 = 30;
+const VIEWPORT_COLS: i32 = 40;
+const VIEWPORT_ROWS: i32 = 30;
+const WORLD_WIDTH: i32 = VIEWPORT_COLS * 4;
+const WORLD_HEIGHT: i32 = VIEWPORT_ROWS * 4;
+
+// Camera configuration
+const BASE_GRID_SCALE: f32 = 20.0;
+const MIN_ZOOM: f32 = 5.0;
+const MAX_ZOOM: f32 = 60.0;
+const ZOOM_STEP: f32 = 4.0;
+const PAN_SPEED: f32 = 15.0;
+
+// Random seeding configuration
+const SEED_DENSITY_MIN: f64 = 0.1;
+const SEED_DENSITY_MAX: f64 = 0.5;
+const SEED_DENSITY_STEP: f64 = 0.1;
+
+// Playback speed configuration
+const MIN_TICK_INTERVAL: u128 = NANOS_PER_TICK / 8;
+const MAX_TICK_INTERVAL: u128 = NANOS_PER_TICK * 8;
+
+// Ecosystem mode configuration
+const GRASS_SPAWN_CHANCE: f64 = 0.01;
+
+// Fractal mode configuration
+const INNER_GRID_SIZE: usize = 5;
+const INNER_SPAWN_THRESHOLD: u8 = 3;
+const INNER_DESPAWN_THRESHOLD: u8 = 1;
+const MAX_NESTING_DEPTH: u32 = 3;
 
 const GRID_COLOR: Color = Color::LIGHTGRAY;
 const LIVE_COLOR: Color = Color::BLACK;
 const DEAD_COLOR: Color = Color::WHITE;
+const GRASS_COLOR: Color = Color::GREEN;
+const PREDATOR_COLOR: Color = Color::RED;
 
 // Helper constants
 const NANOS_PER_TICK: u128 = 1_000_000_000 / TICKS_PER_SECOND;
-const SCREEN_WIDTH: i32 = GRID_WIDTH * GRID_SCALE;
-const SCREEN_HEIGHT: i32 = GRID_HEIGHT * GRID_SCALE;
-const U_GRID_WIDTH: usize = GRID_WIDTH as usize;
-const U_GRID_HEIGHT: usize = GRID_HEIGHT as usize;
+const SCREEN_WIDTH: i32 = VIEWPORT_COLS * BASE_GRID_SCALE as i32;
+const SCREEN_HEIGHT: i32 = VIEWPORT_ROWS * BASE_GRID_SCALE as i32;
+const U_GRID_WIDTH: usize = WORLD_WIDTH as usize;
+const U_GRID_HEIGHT: usize = WORLD_HEIGHT as usize;
 
 enum GameState {
     Editing,
     Running,
 }
 
+/// The viewport's offset (in world-cell units) and zoom (pixels per cell)
+/// used to render a scrollable window onto a world grid larger than the
+/// screen.
+struct Camera {
+    x: f32,
+    y: f32,
+    zoom: f32,
+}
+
+impl Camera {
+    fn new() -> Self {
+        Camera { x: 0.0, y: 0.0, zoom: BASE_GRID_SCALE }
+    }
+
+    /// Number of world cells visible across the screen's width at the
+    /// current zoom level.
+    fn visible_cols(&self) -> f32 {
+        SCREEN_WIDTH as f32 / self.zoom
+    }
+
+    /// Number of world cells visible across the screen's height at the
+    /// current zoom level.
+    fn visible_rows(&self) -> f32 {
+        SCREEN_HEIGHT as f32 / self.zoom
+    }
+
+    /// Shifts the camera by `(dx, dy)` world cells, clamped so the viewport
+    /// never scrolls past the edge of the world grid.
+    fn pan(&mut self, dx: f32, dy: f32) {
+        self.x = (self.x + dx)
+            .clamp(0.0, (WORLD_WIDTH as f32 - self.visible_cols()).max(0.0));
+        self.y = (self.y + dy)
+            .clamp(0.0, (WORLD_HEIGHT as f32 - self.visible_rows()).max(0.0));
+    }
+
+    /// Adjusts zoom by `delta` pixels-per-cell, clamped to
+    /// `[MIN_ZOOM, MAX_ZOOM]`, then re-clamps the camera position since a
+    /// new zoom level changes how many cells are visible.
+    fn zoom_by(&mut self, delta: f32) {
+        self.zoom = (self.zoom + delta).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan(0.0, 0.0);
+    }
+
+    /// Converts a screen pixel position into world grid coordinates.
+    fn screen_to_world(&self, pos: Vector2) -> (usize, usize) {
+        let world_x = self.x + pos.x / self.zoom;
+        let world_y = self.y + pos.y / self.zoom;
+        (
+            (world_x as i32).clamp(0, WORLD_WIDTH - 1) as usize,
+            (world_y as i32).clamp(0, WORLD_HEIGHT - 1) as usize,
+        )
+    }
+}
+
+/// The update rule in effect. `Ecosystem` is a predator-prey variant and
+/// `Fractal` is a recursive nested-grid variant, both selectable from Edit
+/// Mode alongside the standard Conway rule.
+enum SimMode {
+    Conway,
+    Ecosystem,
+    Fractal,
+}
+
+/// A single square of the [`SimMode::Ecosystem`] grid.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Grass,
+    Prey,
+    Predator,
+}
+
+/// A grid of [`SimMode::Fractal`] squares.
+type FractalGrid = Vec<Vec<FractalCell>>;
+
+/// A single square of the [`SimMode::Fractal`] grid. A live cell that
+/// accumulates enough neighbors spawns its own `inner` simulation, which
+/// ticks independently and is dropped once its neighborhood thins back out.
+#[derive(Clone, PartialEq)]
+struct FractalCell {
+    alive: bool,
+    inner: Option<Box<FractalGrid>>,
+}
+
 fn main() {
     let (mut rl, thread) =
         raylib::init().size(SCREEN_WIDTH, SCREEN_HEIGHT).vsync().build();
 
-    let start = Instant::now();
-    let mut current_tick: u128 = 0;
+    let mut last_frame = Instant::now();
+    let mut tick_accum: u128 = 0;
+    let mut tick_interval: u128 = NANOS_PER_TICK;
+    let mut paused = false;
     let mut state = GameState::Editing;
+    let mut mode = SimMode::Conway;
+    let mut wrap = false;
+    let mut prev_square: Option<(usize, usize)> = None;
+    let mut seed_density: f64 = 0.3;
+    let mut eco_brush = Cell::Grass;
+    let mut camera = Camera::new();
 
     let mut grid = new_grid();
+    let mut eco_grid = new_eco_grid();
+    let mut fractal_grid = new_fractal_grid(U_GRID_WIDTH, U_GRID_HEIGHT);
     while !rl.window_should_close() {
+        let now = Instant::now();
+        let frame_delta = now.duration_since(last_frame).as_nanos();
+        last_frame = now;
+
+        // Pan and zoom the camera; this works in both Edit and Automata Mode.
+        let mut pan_dx = 0.0;
+        let mut pan_dy = 0.0;
+        if rl.is_key_down(KeyboardKey::KEY_LEFT) || rl.is_key_down(KeyboardKey::KEY_A)
+        {
+            pan_dx -= 1.0;
+        }
+        if rl.is_key_down(KeyboardKey::KEY_RIGHT) || rl.is_key_down(KeyboardKey::KEY_D)
+        {
+            pan_dx += 1.0;
+        }
+        if rl.is_key_down(KeyboardKey::KEY_UP) || rl.is_key_down(KeyboardKey::KEY_W) {
+            pan_dy -= 1.0;
+        }
+        if rl.is_key_down(KeyboardKey::KEY_DOWN) || rl.is_key_down(KeyboardKey::KEY_S) {
+            pan_dy += 1.0;
+        }
+        if pan_dx != 0.0 || pan_dy != 0.0 {
+            let elapsed_secs = frame_delta as f32 / 1_000_000_000.0;
+            camera.pan(pan_dx * PAN_SPEED * elapsed_secs, pan_dy * PAN_SPEED * elapsed_secs);
+        }
+        let wheel = rl.get_mouse_wheel_move();
+        if wheel != 0.0 {
+            camera.zoom_by(wheel * ZOOM_STEP);
+        }
+
         match state {
             GameState::Editing => {
-                // Use mouse to set squares
-                if rl.is_mouse_button_down(MOUSE_LEFT_BUTTON) {
-                    let (x, y) = px_to_square(rl.get_mouse_position());
-                    grid[y][x] = true;
+                // Use mouse to set squares, rasterizing a line back to the
+                // previously sampled square so fast drags don't leave gaps.
+                let paint = if rl.is_mouse_button_down(MOUSE_LEFT_BUTTON) {
+                    Some(true)
                 } else if rl.is_mouse_button_down(MOUSE_RIGHT_BUTTON) {
-                    let (x, y) = px_to_square(rl.get_mouse_position());
-                    grid[y][x] = false;
+                    Some(false)
+                } else {
+                    None
+                };
+
+                if let Some(paint) = paint {
+                    let square = camera.screen_to_world(rl.get_mouse_position());
+                    for (x, y) in line_squares(prev_square.unwrap_or(square), square) {
+                        match mode {
+                            SimMode::Conway => grid[y][x] = paint,
+                            SimMode::Ecosystem => {
+                                eco_grid[y][x] =
+                                    if paint { eco_brush } else { Cell::Empty };
+                            }
+                            SimMode::Fractal => {
+                                fractal_grid[y][x] = FractalCell {
+                                    alive: paint,
+                                    inner: None,
+                                };
+                            }
+                        }
+                    }
+                    prev_square = Some(square);
+                } else {
+                    prev_square = None;
                 }
 
                 match rl.get_key_pressed() {
-                    Some(KeyboardKey::KEY_SPACE) => state = GameState::Running,
-                    Some(KeyboardKey::KEY_X) => grid = new_grid(),
+                    Some(KeyboardKey::KEY_SPACE) => {
+                        state = GameState::Running;
+                        tick_accum = 0;
+                    }
+                    Some(KeyboardKey::KEY_X) => match mode {
+                        SimMode::Conway => grid = new_grid(),
+                        SimMode::Ecosystem => eco_grid = new_eco_grid(),
+                        SimMode::Fractal => {
+                            fractal_grid = new_fractal_grid(U_GRID_WIDTH, U_GRID_HEIGHT);
+                        }
+                    },
+                    Some(KeyboardKey::KEY_T) => wrap = !wrap,
+                    Some(KeyboardKey::KEY_M) => {
+                        mode = match mode {
+                            SimMode::Conway => SimMode::Ecosystem,
+                            SimMode::Ecosystem => SimMode::Fractal,
+                            SimMode::Fractal => SimMode::Conway,
+                        };
+                    }
+                    Some(KeyboardKey::KEY_R) if matches!(mode, SimMode::Conway) => {
+                        grid = random_grid(seed_density);
+                    }
+                    Some(KeyboardKey::KEY_C) if matches!(mode, SimMode::Ecosystem) => {
+                        eco_brush = match eco_brush {
+                            Cell::Grass => Cell::Prey,
+                            Cell::Prey => Cell::Predator,
+                            Cell::Predator | Cell::Empty => Cell::Grass,
+                        };
+                    }
+                    Some(KeyboardKey::KEY_EQUAL) => {
+                        seed_density =
+                            (seed_density + SEED_DENSITY_STEP).min(SEED_DENSITY_MAX);
+                    }
+                    Some(KeyboardKey::KEY_MINUS) => {
+                        seed_density =
+                            (seed_density - SEED_DENSITY_STEP).max(SEED_DENSITY_MIN);
+                    }
                     _ => (),
                 }
             }
             GameState::Running => {
-                let tick = start.elapsed().as_nanos() / NANOS_PER_TICK;
+                let mut should_tick = false;
+                if !paused {
+                    tick_accum += frame_delta;
+                    if tick_accum >= tick_interval {
+                        tick_accum -= tick_interval;
+                        should_tick = true;
+                    }
+                }
 
-                if tick > current_tick {
-                    current_tick = tick;
+                match rl.get_key_pressed() {
+                    Some(KeyboardKey::KEY_SPACE) => state = GameState::Editing,
+                    Some(KeyboardKey::KEY_P) => paused = !paused,
+                    Some(KeyboardKey::KEY_PERIOD) if paused => should_tick = true,
+                    Some(KeyboardKey::KEY_LEFT_BRACKET) => {
+                        tick_interval = (tick_interval / 2).max(MIN_TICK_INTERVAL);
+                    }
+                    Some(KeyboardKey::KEY_RIGHT_BRACKET) => {
+                        tick_interval = (tick_interval * 2).min(MAX_TICK_INTERVAL);
+                    }
+                    _ => (),
+                }
 
-                    let mut next_grid = new_grid();
-                    let mut num_alive = 0;
-                    let mut still_frame = true;
+                if should_tick {
+                    match mode {
+                        SimMode::Conway => {
+                            let mut next_grid = new_grid();
+                            let mut num_alive = 0;
+                            let mut still_frame = true;
 
-                    for x in 0..U_GRID_WIDTH {
-                        for y in 0..U_GRID_HEIGHT {
-                            let neighbor_count = living_neighbors(&grid, x, y);
+                            for x in 0..U_GRID_WIDTH {
+                                for y in 0..U_GRID_HEIGHT {
+                                    let neighbor_count =
+                                        living_neighbors(&grid, x, y, wrap);
 
-                            if grid[y][x] {
-                                if neighbor_count == 2 || neighbor_count == 3 {
-                                    next_grid[y][x] = true;
-                                    num_alive += 1;
-                                } else {
-                                    still_frame = false;
+                                    if grid[y][x] {
+                                        if neighbor_count == 2 || neighbor_count == 3
+                                        {
+                                            next_grid[y][x] = true;
+                                            num_alive += 1;
+                                        } else {
+                                            still_frame = false;
+                                        }
+                                    } else if neighbor_count == 3 {
+                                        next_grid[y][x] = true;
+                                        num_alive += 1;
+                                        still_frame = false;
+                                    }
                                 }
-                            } else if neighbor_count == 3 {
-                                next_grid[y][x] = true;
-                                num_alive += 1;
-                                still_frame = false;
                             }
+
+                            if still_frame || num_alive == 0 {
+                                state = GameState::Editing;
+                            }
+
+                            grid = next_grid;
                         }
-                    }
+                        SimMode::Ecosystem => {
+                            let next_eco_grid = step_ecosystem(&eco_grid, wrap);
+                            let num_alive = next_eco_grid
+                                .iter()
+                                .flatten()
+                                .filter(|&&c| c != Cell::Empty)
+                                .count();
+                            let still_frame = next_eco_grid == eco_grid;
 
-                    if still_frame || num_alive == 0 {
-                        state = GameState::Editing;
-                    }
+                            if still_frame || num_alive == 0 {
+                                state = GameState::Editing;
+                            }
 
-                    grid = next_grid;
-                }
+                            eco_grid = next_eco_grid;
+                        }
+                        SimMode::Fractal => {
+                            let next_fractal_grid = step_fractal(&fractal_grid, wrap, 0);
+                            let num_alive = next_fractal_grid
+                                .iter()
+                                .flatten()
+                                .filter(|c| c.alive)
+                                .count();
+                            let still_frame = next_fractal_grid == fractal_grid;
 
-                if let Some(KeyboardKey::KEY_SPACE) = rl.get_key_pressed() {
-                    state = GameState::Editing;
+                            if still_frame || num_alive == 0 {
+                                state = GameState::Editing;
+                            }
+
+                            fractal_grid = next_fractal_grid;
+                        }
+                    }
                 }
             }
         }
 
         let mut d = rl.begin_drawing(&thread);
 
+        // The range of world cells currently within the viewport.
+        let x_range = camera.x.floor() as i32
+            ..(camera.x + camera.visible_cols()).ceil().min(WORLD_WIDTH as f32) as i32;
+        let y_range = camera.y.floor() as i32
+            ..(camera.y + camera.visible_rows()).ceil().min(WORLD_HEIGHT as f32) as i32;
+        let cell_size = camera.zoom.ceil() as i32;
+
         // Draw squares
-        for x in 0..GRID_WIDTH {
-            for y in 0..GRID_HEIGHT {
-                let alive = grid[y as usize][x as usize];
-                d.draw_rectangle(
-                    x * GRID_SCALE,
-                    y * GRID_SCALE,
-                    GRID_SCALE,
-                    GRID_SCALE,
-                    if alive { LIVE_COLOR } else { DEAD_COLOR },
-                );
+        for x in x_range.clone() {
+            for y in y_range.clone() {
+                let screen_x = ((x as f32 - camera.x) * camera.zoom) as i32;
+                let screen_y = ((y as f32 - camera.y) * camera.zoom) as i32;
+                let (x, y) = (x as usize, y as usize);
+
+                match mode {
+                    SimMode::Conway => {
+                        let color =
+                            if grid[y][x] { LIVE_COLOR } else { DEAD_COLOR };
+                        d.draw_rectangle(
+                            screen_x, screen_y, cell_size, cell_size, color,
+                        );
+                    }
+                    SimMode::Ecosystem => {
+                        let color = match eco_grid[y][x] {
+                            Cell::Empty => DEAD_COLOR,
+                            Cell::Grass => GRASS_COLOR,
+                            Cell::Prey => LIVE_COLOR,
+                            Cell::Predator => PREDATOR_COLOR,
+                        };
+                        d.draw_rectangle(
+                            screen_x, screen_y, cell_size, cell_size, color,
+                        );
+                    }
+                    SimMode::Fractal => draw_fractal_cell(
+                        &mut d,
+                        &fractal_grid[y][x],
+                        screen_x,
+                        screen_y,
+                        cell_size,
+                        cell_size,
+                    ),
+                }
             }
         }
 
         // Draw grid lines
-        for i in 1..GRID_WIDTH {
-            let x_px = i * GRID_SCALE;
+        for i in x_range {
+            let x_px = ((i as f32 - camera.x) * camera.zoom) as i32;
             d.draw_line(x_px, 0, x_px, SCREEN_HEIGHT, GRID_COLOR);
         }
-        for j in 1..GRID_HEIGHT {
-            let y_px = j * GRID_SCALE;
+        for j in y_range {
+            let y_px = ((j as f32 - camera.y) * camera.zoom) as i32;
             d.draw_line(0, y_px, SCREEN_WIDTH, y_px, GRID_COLOR);
         }
 
         if let GameState::Editing = state {
             d.draw_text("Edit Mode", 15, 15, 25, Color::DARKBLUE);
+            d.draw_text(
+                &format!("Seed density: {seed_density:.1}"),
+                15,
+                70,
+                20,
+                Color::DARKBLUE,
+            );
+            match mode {
+                SimMode::Ecosystem => {
+                    let brush = match eco_brush {
+                        Cell::Grass => "Grass",
+                        Cell::Prey => "Prey",
+                        Cell::Predator => "Predator",
+                        Cell::Empty => unreachable!("brush is never Empty"),
+                    };
+                    d.draw_text(
+                        &format!("Ecosystem Mode ({brush})"),
+                        15,
+                        95,
+                        20,
+                        Color::DARKBLUE,
+                    );
+                }
+                SimMode::Fractal => {
+                    d.draw_text("Fractal Mode", 15, 95, 20, Color::DARKBLUE);
+                }
+                SimMode::Conway => (),
+            }
+        }
+        if wrap {
+            d.draw_text("Wrap: on", 15, 45, 20, Color::DARKBLUE);
+        }
+        if let GameState::Running = state {
+            if paused {
+                d.draw_text("Paused", 15, 15, 25, Color::DARKBLUE);
+            }
         }
     }
 }
 
-/// Transforms vector pixel coordinates into indices on the automata grid.
-fn px_to_square(Vector2 { x, y }: Vector2) -> (usize, usize) {
-    let x = (x as i32).clamp(0, SCREEN_WIDTH - GRID_SCALE);
-    let y = (y as i32).clamp(0, SCREEN_HEIGHT - GRID_SCALE);
-    let x = x - (x % GRID_SCALE);
-    let y = y - (y % GRID_SCALE);
+/// Returns the grid squares on the line between `from` and `to`, inclusive,
+/// using Bresenham's line algorithm.
+fn line_squares(from: (usize, usize), to: (usize, usize)) -> Vec<(usize, usize)> {
+    let (x0, y0) = (from.0 as i32, from.1 as i32);
+    let (x1, y1) = (to.0 as i32, to.1 as i32);
+
+    let dx = (x1 - x0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let dy = -(y1 - y0).abs();
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
 
-    let x = x / GRID_SCALE;
-    let y = y / GRID_SCALE;
-    (x as usize, y as usize)
+    let mut squares = Vec::new();
+    let (mut x, mut y) = (x0, y0);
+    loop {
+        squares.push((x as usize, y as usize));
+        if x == x1 && y == y1 {
+            break;
+        }
+
+        let error2 = 2 * error;
+        if error2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if error2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+
+    squares
 }
 
 /// Returns a new automata grid filled with dead squares.
@@ -165,24 +524,261 @@ fn new_grid() -> Vec<Vec<bool>> {
     vec![vec![false; U_GRID_WIDTH]; U_GRID_HEIGHT]
 }
 
-/// Returns the number of living neighbors which square `(x, y)` has in `grid`.
-fn living_neighbors(grid: &[Vec<bool>], x: usize, y: usize) -> u8 {
-    let left = if x == 0 { x } else { x - 1 };
-    let right = if x == U_GRID_WIDTH - 1 { x } else { x + 1 };
-    let above = if y == 0 { y } else { y - 1 };
-    let below = if y == U_GRID_HEIGHT - 1 { y } else { y + 1 };
+/// Returns a new automata grid seeded from a Bernoulli distribution, where
+/// each square is independently alive with probability `density`.
+fn random_grid(density: f64) -> Vec<Vec<bool>> {
+    let mut rng = rand::thread_rng();
+    let mut grid = new_grid();
+    for row in grid.iter_mut() {
+        for square in row.iter_mut() {
+            *square = rng.gen_bool(density);
+        }
+    }
+    grid
+}
+
+/// Returns the grid coordinates of the 8 squares surrounding `(x, y)` in a
+/// grid of size `width` by `height`.
+///
+/// When `wrap` is `true`, the grid is treated as a torus: neighbors off one
+/// edge are taken from the opposite edge. When `wrap` is `false`, the grid is
+/// bounded and coordinates past the border are clamped back to the border.
+fn neighbor_coords(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    wrap: bool,
+) -> [(usize, usize); 8] {
+    let (left, right, above, below) = if wrap {
+        (
+            (x + width - 1) % width,
+            (x + 1) % width,
+            (y + height - 1) % height,
+            (y + 1) % height,
+        )
+    } else {
+        (
+            if x == 0 { x } else { x - 1 },
+            if x == width - 1 { x } else { x + 1 },
+            if y == 0 { y } else { y - 1 },
+            if y == height - 1 { y } else { y + 1 },
+        )
+    };
 
     [
-        grid[y][left],
-        grid[y][right],
-        grid[above][x],
-        grid[below][x],
-        grid[above][left],
-        grid[above][right],
-        grid[below][left],
-        grid[below][right],
+        (left, y),
+        (right, y),
+        (x, above),
+        (x, below),
+        (left, above),
+        (right, above),
+        (left, below),
+        (right, below),
     ]
-    .into_iter()
-    .map(|b| b as u8)
-    .sum()
+}
+
+/// Returns a new ecosystem grid filled with empty squares.
+fn new_eco_grid() -> Vec<Vec<Cell>> {
+    vec![vec![Cell::Empty; U_GRID_WIDTH]; U_GRID_HEIGHT]
+}
+
+/// Computes the next generation of the predator-prey [`SimMode::Ecosystem`]
+/// rule, applied per cell over its 8 neighbors in `grid`: Grass spreads into
+/// an Empty cell if any neighbor is Grass, but is itself consumed to Empty if
+/// a Prey is adjacent. Prey survives if Grass is adjacent (consuming it) and
+/// starves to Empty otherwise, but is itself consumed to Empty if a Predator
+/// is adjacent. Predator survives if Prey is adjacent (consuming it) and
+/// starves to Empty otherwise. Grass also has a small chance to spontaneously
+/// spawn on an Empty cell, so the ecosystem doesn't collapse entirely once
+/// neighboring grass runs out.
+///
+/// The result is computed entirely from `grid`, so consumption, starvation,
+/// and spread all resolve in the same tick, the same double-buffering
+/// invariant the Conway rule relies on.
+fn step_ecosystem(grid: &[Vec<Cell>], wrap: bool) -> Vec<Vec<Cell>> {
+    let mut rng = rand::thread_rng();
+    let mut next = new_eco_grid();
+
+    for x in 0..U_GRID_WIDTH {
+        for y in 0..U_GRID_HEIGHT {
+            let has_neighbor = |species: Cell| {
+                neighbor_coords(x, y, U_GRID_WIDTH, U_GRID_HEIGHT, wrap)
+                    .into_iter()
+                    .any(|(nx, ny)| grid[ny][nx] == species)
+            };
+
+            next[y][x] = match grid[y][x] {
+                Cell::Predator => {
+                    if has_neighbor(Cell::Prey) {
+                        Cell::Predator
+                    } else {
+                        Cell::Empty
+                    }
+                }
+                Cell::Prey => {
+                    if has_neighbor(Cell::Predator) {
+                        Cell::Empty
+                    } else if has_neighbor(Cell::Grass) {
+                        Cell::Prey
+                    } else {
+                        Cell::Empty
+                    }
+                }
+                Cell::Grass => {
+                    if has_neighbor(Cell::Prey) {
+                        Cell::Empty
+                    } else {
+                        Cell::Grass
+                    }
+                }
+                Cell::Empty if has_neighbor(Cell::Grass) => Cell::Grass,
+                Cell::Empty if rng.gen_bool(GRASS_SPAWN_CHANCE) => Cell::Grass,
+                Cell::Empty => Cell::Empty,
+            };
+        }
+    }
+
+    next
+}
+
+/// Returns the number of living neighbors which square `(x, y)` has in `grid`.
+///
+/// See [`neighbor_coords`] for how `wrap` affects border behavior.
+fn living_neighbors(grid: &[Vec<bool>], x: usize, y: usize, wrap: bool) -> u8 {
+    neighbor_coords(x, y, U_GRID_WIDTH, U_GRID_HEIGHT, wrap)
+        .into_iter()
+        .map(|(nx, ny)| grid[ny][nx] as u8)
+        .sum()
+}
+
+/// Returns a new `width` by `height` [`SimMode::Fractal`] grid with no
+/// live squares and no inner grids.
+fn new_fractal_grid(width: usize, height: usize) -> FractalGrid {
+    vec![
+        vec![FractalCell { alive: false, inner: None }; width];
+        height
+    ]
+}
+
+/// Computes the next generation of the [`SimMode::Fractal`] rule for `grid`.
+///
+/// The standard B3/S23 Conway rule is applied first. Then, for every live
+/// cell, its live-neighbor count (from this same previous generation, not
+/// the half-updated result) decides what happens to its `inner` grid: at
+/// `INNER_SPAWN_THRESHOLD` or above a cell with no inner grid allocates a
+/// fresh `INNER_GRID_SIZE`-square one seeded from its neighborhood; at or
+/// below `INNER_DESPAWN_THRESHOLD` any inner grid is dropped; otherwise an
+/// existing inner grid ticks forward one generation of its own. `depth`
+/// bounds how many tiers may nest, so a cell at `MAX_NESTING_DEPTH` never
+/// spawns another tier.
+fn step_fractal(grid: &FractalGrid, wrap: bool, depth: u32) -> FractalGrid {
+    let height = grid.len();
+    let width = grid.first().map_or(0, Vec::len);
+    let mut next = new_fractal_grid(width, height);
+
+    for x in 0..width {
+        for y in 0..height {
+            let neighbor_count = neighbor_coords(x, y, width, height, wrap)
+                .into_iter()
+                .map(|(nx, ny)| grid[ny][nx].alive as u8)
+                .sum::<u8>();
+
+            let alive = if grid[y][x].alive {
+                neighbor_count == 2 || neighbor_count == 3
+            } else {
+                neighbor_count == 3
+            };
+
+            let inner = if !alive
+                || depth >= MAX_NESTING_DEPTH
+                || neighbor_count <= INNER_DESPAWN_THRESHOLD
+            {
+                None
+            } else if let Some(inner) = &grid[y][x].inner {
+                Some(Box::new(step_fractal(inner, wrap, depth + 1)))
+            } else if neighbor_count >= INNER_SPAWN_THRESHOLD {
+                Some(Box::new(seed_inner_fractal_grid(grid, x, y, wrap)))
+            } else {
+                None
+            };
+
+            next[y][x] = FractalCell { alive, inner };
+        }
+    }
+
+    next
+}
+
+/// Seeds a new `INNER_GRID_SIZE`-square inner grid for the cell at `(x, y)`
+/// in `grid`, tiling the cell's own 3x3 neighborhood across it.
+fn seed_inner_fractal_grid(
+    grid: &FractalGrid,
+    x: usize,
+    y: usize,
+    wrap: bool,
+) -> FractalGrid {
+    let width = grid[0].len();
+    let height = grid.len();
+    let nearby = |dx: i32, dy: i32| -> bool {
+        let nx = wrap_offset(x, dx, width, wrap);
+        let ny = wrap_offset(y, dy, height, wrap);
+        grid[ny][nx].alive
+    };
+
+    let mut pattern = [[false; 3]; 3];
+    for (dy, row) in pattern.iter_mut().enumerate() {
+        for (dx, alive) in row.iter_mut().enumerate() {
+            *alive = nearby(dx as i32 - 1, dy as i32 - 1);
+        }
+    }
+
+    let mut inner = new_fractal_grid(INNER_GRID_SIZE, INNER_GRID_SIZE);
+    for (iy, row) in inner.iter_mut().enumerate() {
+        for (ix, cell) in row.iter_mut().enumerate() {
+            cell.alive = pattern[iy % 3][ix % 3];
+        }
+    }
+    inner
+}
+
+/// Offsets `v` by `delta` within `0..dim`, wrapping or clamping to match
+/// [`neighbor_coords`]'s treatment of the `wrap` flag.
+fn wrap_offset(v: usize, delta: i32, dim: usize, wrap: bool) -> usize {
+    let v = v as i32 + delta;
+    if wrap {
+        v.rem_euclid(dim as i32) as usize
+    } else {
+        v.clamp(0, dim as i32 - 1) as usize
+    }
+}
+
+/// Draws `cell` as a `w`-by-`h` pixel square at `(px, py)`, recursively
+/// subdividing the rectangle to draw its `inner` grid, if any.
+fn draw_fractal_cell(
+    d: &mut impl RaylibDraw,
+    cell: &FractalCell,
+    px: i32,
+    py: i32,
+    w: i32,
+    h: i32,
+) {
+    d.draw_rectangle(px, py, w, h, if cell.alive { LIVE_COLOR } else { DEAD_COLOR });
+
+    if let Some(inner) = &cell.inner {
+        let inner_w = (w / INNER_GRID_SIZE as i32).max(1);
+        let inner_h = (h / INNER_GRID_SIZE as i32).max(1);
+        for (iy, row) in inner.iter().enumerate() {
+            for (ix, inner_cell) in row.iter().enumerate() {
+                draw_fractal_cell(
+                    d,
+                    inner_cell,
+                    px + ix as i32 * inner_w,
+                    py + iy as i32 * inner_h,
+                    inner_w,
+                    inner_h,
+                );
+            }
+        }
+    }
 }